@@ -1,16 +1,22 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use anyhow::{Context, Result};
-use chrono::{Duration, Utc};
-use rusqlite::{params, Connection};
-use serde::Serialize;
-use tauri::{AppHandle, Manager};
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc};
+use rusqlite::{params, Connection, DatabaseName, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
 
 const DB_FILENAME: &str = "aurum.sqlite3";
 const DEFAULT_ACCOUNT_NAME: &str = "Primary Checking";
 const DEFAULT_ACCOUNT_TYPE: &str = "current";
+const DEFAULT_BASE_CURRENCY: &str = "USD";
 const DEFAULT_ACCOUNT_BALANCE: f64 = 2_500.0;
+const FORECAST_HORIZON_DAYS: i64 = 30;
+const BUSY_TIMEOUT_MS: u32 = 5_000;
+const BUDGET_GRACE_FRACTION: f64 = 0.9;
 
 const SCHEMA_SQL: &str = r#"
 CREATE TABLE IF NOT EXISTS accounts (
@@ -52,12 +58,132 @@ CREATE TABLE IF NOT EXISTS budgets (
 );
 "#;
 
+/// A single forward-only schema migration. Steps are applied in ascending
+/// `version` order; anything at or below the database's current
+/// `PRAGMA user_version` is skipped.
+struct Migration {
+  version: i64,
+  sql: &'static str,
+}
+
+/// Migration 2: multi-currency support. Tags every account with a currency,
+/// records the user's base currency in a key/value `settings` table, and adds
+/// an `fx_rates` table for converting native balances into the base currency.
+const MIGRATION_0002_SQL: &str = r#"
+ALTER TABLE accounts ADD COLUMN currency TEXT NOT NULL DEFAULT 'USD';
+
+CREATE TABLE IF NOT EXISTS settings (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL
+);
+
+INSERT OR IGNORE INTO settings (key, value) VALUES ('base_currency', 'USD');
+
+CREATE TABLE IF NOT EXISTS fx_rates (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  from_currency TEXT NOT NULL,
+  to_currency TEXT NOT NULL,
+  rate REAL NOT NULL,
+  as_of TEXT NOT NULL
+);
+"#;
+
+/// Migration 3: tag scheduled items with a spending category so the budget
+/// outlook can attribute projected cash flow to `budgets.category`.
+const MIGRATION_0003_SQL: &str = r#"
+ALTER TABLE scheduled_items ADD COLUMN category TEXT;
+"#;
+
+/// The ordered migration list. Append new steps here with the next version
+/// number — never edit or reorder an already-shipped migration.
+const MIGRATIONS: &[Migration] = &[
+  Migration {
+    version: 1,
+    sql: SCHEMA_SQL,
+  },
+  Migration {
+    version: 2,
+    sql: MIGRATION_0002_SQL,
+  },
+  Migration {
+    version: 3,
+    sql: MIGRATION_0003_SQL,
+  },
+];
+
 #[derive(Debug, Clone, Serialize)]
 struct ForecastPoint {
   date: String,
   balance: f64,
 }
 
+/// A liquid or illiquid account as held in the running simulation. `balance`
+/// is loaded in the account's native `currency`; callers convert to the base
+/// currency before summing across accounts (the forecast restates it in the
+/// base currency in place before simulating, leaving `currency` as the native
+/// code).
+#[derive(Debug, Clone)]
+struct SimAccount {
+  balance: f64,
+  currency: String,
+  is_liquid: bool,
+  growth_rate_apr: f64,
+}
+
+/// An account paired with both its native and base-currency balance, for the
+/// `list_accounts` command.
+#[derive(Debug, Clone, Serialize)]
+struct AccountBalance {
+  id: i64,
+  name: String,
+  currency: String,
+  native_balance: f64,
+  base_balance: f64,
+}
+
+/// The full account listing returned to the frontend.
+#[derive(Debug, Clone, Serialize)]
+struct AccountsView {
+  base_currency: String,
+  total_liquid_base: f64,
+  accounts: Vec<AccountBalance>,
+}
+
+/// One exchange rate as supplied to the refresh command: `rate` units of
+/// `to_currency` per single unit of `from_currency`.
+#[derive(Debug, Clone, Deserialize)]
+struct FxRate {
+  from_currency: String,
+  to_currency: String,
+  rate: f64,
+}
+
+/// A recurring cash-flow event from the `scheduled_items` table, with its
+/// `next_date` already rolled forward to the first on-or-after-today occurrence.
+#[derive(Debug, Clone)]
+struct ScheduledItem {
+  account_id: i64,
+  amount: f64,
+  frequency: String,
+  next_date: NaiveDate,
+  kind: String,
+  target_account_id: Option<i64>,
+  category: Option<String>,
+}
+
+/// Projected standing of one budget category over the forecast horizon.
+#[derive(Debug, Clone, Serialize)]
+struct BudgetStatus {
+  category: String,
+  monthly_limit: f64,
+  /// Worst single calendar month's cumulative spend within the horizon, in the
+  /// base currency. Spend resets at each month boundary, so this is the peak of
+  /// the per-month totals rather than a sum across the whole horizon.
+  projected_spend: f64,
+  at_risk: bool,
+  projected_overage_date: Option<String>,
+}
+
 fn database_path(app: &AppHandle) -> Result<PathBuf> {
   let app_data_dir = app
     .path()
@@ -70,12 +196,54 @@ fn database_path(app: &AppHandle) -> Result<PathBuf> {
   Ok(app_data_dir.join(DB_FILENAME))
 }
 
-fn bootstrap_database_at(db_path: &Path) -> Result<()> {
+/// Apply every migration whose version is greater than the database's current
+/// `user_version`, each inside its own transaction, then stamp `user_version`
+/// to the highest version applied.
+fn run_migrations(conn: &mut Connection) -> Result<()> {
+  let current: i64 = conn
+    .query_row("PRAGMA user_version", [], |row| row.get(0))
+    .context("failed reading schema version")?;
+
+  for migration in MIGRATIONS {
+    if migration.version <= current {
+      continue;
+    }
+
+    let tx = conn.transaction().context("failed starting migration transaction")?;
+    tx.execute_batch(migration.sql)
+      .with_context(|| format!("failed applying migration {}", migration.version))?;
+    // `user_version` does not accept bind parameters; the value is a trusted
+    // constant from MIGRATIONS, so formatting it in is safe.
+    tx.execute_batch(&format!("PRAGMA user_version = {}", migration.version))
+      .with_context(|| format!("failed stamping schema version {}", migration.version))?;
+    tx.commit()
+      .with_context(|| format!("failed committing migration {}", migration.version))?;
+  }
+
+  Ok(())
+}
+
+/// Open the database and apply the per-connection PRAGMAs the app relies on:
+/// `foreign_keys` (OFF by default in SQLite, so the schema's FK constraints are
+/// otherwise never enforced), a `busy_timeout` to ride out concurrent writers,
+/// and WAL journalling for better read/write concurrency.
+fn open_connection(db_path: &Path) -> Result<Connection> {
   let conn = Connection::open(db_path)
     .with_context(|| format!("failed opening sqlite database at {}", db_path.display()))?;
 
-  conn.execute_batch(SCHEMA_SQL)
-    .context("failed creating schema")?;
+  conn
+    .execute_batch(&format!(
+      "PRAGMA foreign_keys = ON; PRAGMA busy_timeout = {BUSY_TIMEOUT_MS}; PRAGMA journal_mode = WAL;"
+    ))
+    .context("failed applying connection pragmas")?;
+
+  Ok(conn)
+}
+
+fn bootstrap_database_at(db_path: &Path) -> Result<()> {
+  let mut conn = open_connection(db_path)?;
+
+  run_migrations(&mut conn).context("failed running migrations")?;
 
   let account_count: i64 = conn
     .query_row("SELECT COUNT(1) FROM accounts", [], |row| row.get(0))
@@ -98,44 +266,526 @@ fn bootstrap_database_at(db_path: &Path) -> Result<()> {
   Ok(())
 }
 
-fn liquid_starting_balance(db_path: &Path) -> Result<f64> {
-  let conn = Connection::open(db_path)
-    .with_context(|| format!("failed opening sqlite database at {}", db_path.display()))?;
+/// Read the user's configured base currency, falling back to the default if the
+/// setting row is somehow absent.
+fn base_currency(conn: &Connection) -> Result<String> {
+  let stored: Option<String> = conn
+    .query_row(
+      "SELECT value FROM settings WHERE key = 'base_currency'",
+      [],
+      |row| row.get(0),
+    )
+    .optional()
+    .context("failed reading base currency")?;
+
+  Ok(stored.unwrap_or_else(|| DEFAULT_BASE_CURRENCY.to_string()))
+}
+
+/// Convert `amount` from `currency` into `base` using the most recent stored
+/// rate. A currency equal to the base is the identity; a missing rate is an error
+/// so balances are never silently summed across unconvertible currencies.
+fn convert_to_base(conn: &Connection, amount: f64, currency: &str, base: &str) -> Result<f64> {
+  if currency == base {
+    return Ok(amount);
+  }
+
+  let latest_rate = |from: &str, to: &str| -> Result<Option<f64>> {
+    conn
+      .query_row(
+        "SELECT rate FROM fx_rates WHERE from_currency = ?1 AND to_currency = ?2 \
+         ORDER BY as_of DESC, id DESC LIMIT 1",
+        params![from, to],
+        |row| row.get(0),
+      )
+      .optional()
+      .context("failed reading exchange rate")
+  };
+
+  // Prefer a direct rate; fall back to inverting the reverse pair so a single
+  // stored direction is enough to convert either way.
+  if let Some(rate) = latest_rate(currency, base)? {
+    return Ok(amount * rate);
+  }
+  if let Some(inverse) = latest_rate(base, currency)? {
+    if inverse != 0.0 {
+      return Ok(amount / inverse);
+    }
+  }
+
+  Err(anyhow!("no exchange rate from {currency} to {base}"))
+}
+
+fn liquid_starting_balance(conn: &Connection) -> Result<f64> {
+  let base = base_currency(conn)?;
+  let accounts = load_accounts(conn)?;
 
-  conn.query_row(
-    "SELECT COALESCE(SUM(balance), 0) FROM accounts WHERE is_liquid = 1",
-    [],
-    |row| row.get(0),
-  )
-  .context("failed reading liquid account balance")
+  let mut total = 0.0;
+  for account in accounts.values().filter(|account| account.is_liquid) {
+    total += convert_to_base(conn, account.balance, &account.currency, &base)?;
+  }
+
+  Ok(total)
+}
+
+/// Advance a date by one step of the given recurrence `frequency`. Monthly and
+/// yearly steps clamp to month-end (e.g. Jan 31 + 1 month -> Feb 28/29), which
+/// is exactly the behaviour `chrono::Months` provides.
+fn advance_date(date: NaiveDate, frequency: &str) -> NaiveDate {
+  match frequency {
+    "weekly" => date + Duration::days(7),
+    "monthly" => date + Months::new(1),
+    "yearly" => date + Months::new(12),
+    // "daily" and anything unrecognised fall back to a single day so that a
+    // stray frequency never stalls the simulation loop.
+    _ => date + Duration::days(1),
+  }
+}
+
+fn load_accounts(conn: &Connection) -> Result<HashMap<i64, SimAccount>> {
+  let mut statement = conn
+    .prepare("SELECT id, balance, currency, is_liquid, COALESCE(growth_rate_apr, 0) FROM accounts")
+    .context("failed preparing account query")?;
+
+  let rows = statement
+    .query_map([], |row| {
+      Ok((
+        row.get::<_, i64>(0)?,
+        SimAccount {
+          balance: row.get(1)?,
+          currency: row.get(2)?,
+          is_liquid: row.get::<_, i64>(3)? != 0,
+          growth_rate_apr: row.get(4)?,
+        },
+      ))
+    })
+    .context("failed reading accounts")?;
+
+  rows
+    .collect::<std::result::Result<HashMap<_, _>, _>>()
+    .context("failed collecting accounts")
+}
+
+fn load_scheduled_items(conn: &Connection, today: NaiveDate) -> Result<Vec<ScheduledItem>> {
+  let mut statement = conn
+    .prepare(
+      "SELECT account_id, amount, frequency, next_date, type, target_account_id, category FROM scheduled_items",
+    )
+    .context("failed preparing scheduled item query")?;
+
+  let rows = statement
+    .query_map([], |row| {
+      let raw_date: String = row.get(3)?;
+      Ok((
+        raw_date,
+        ScheduledItem {
+          account_id: row.get(0)?,
+          amount: row.get(1)?,
+          frequency: row.get(2)?,
+          next_date: today,
+          kind: row.get(4)?,
+          target_account_id: row.get(5)?,
+          category: row.get(6)?,
+        },
+      ))
+    })
+    .context("failed reading scheduled items")?;
+
+  let mut items = Vec::new();
+  for row in rows {
+    let (raw_date, mut item) = row.context("failed decoding scheduled item")?;
+    let parsed = NaiveDate::parse_from_str(&raw_date, "%Y-%m-%d")
+      .with_context(|| format!("invalid scheduled_items.next_date: {raw_date}"))?;
+
+    // Roll a past due date forward to the first occurrence that is on or after
+    // today; the simulation only fires items whose date matches a simulated day,
+    // so an unrolled back-dated item would never fire.
+    let mut next = parsed;
+    while next < today {
+      next = advance_date(next, &item.frequency);
+    }
+    item.next_date = next;
+    items.push(item);
+  }
+
+  Ok(items)
 }
 
-fn build_forecast_points(start_balance: f64) -> Vec<ForecastPoint> {
+/// Apply a single scheduled item's effect to the in-memory account balances.
+fn apply_scheduled_item(accounts: &mut HashMap<i64, SimAccount>, item: &ScheduledItem) {
+  match item.kind.as_str() {
+    "income" => {
+      if let Some(account) = accounts.get_mut(&item.account_id) {
+        account.balance += item.amount;
+      }
+    }
+    "expense" => {
+      if let Some(account) = accounts.get_mut(&item.account_id) {
+        account.balance -= item.amount;
+      }
+    }
+    "transfer" => {
+      if let Some(target_id) = item.target_account_id {
+        if let Some(source) = accounts.get_mut(&item.account_id) {
+          source.balance -= item.amount;
+        }
+        if let Some(target) = accounts.get_mut(&target_id) {
+          target.balance += item.amount;
+        }
+      }
+    }
+    _ => {}
+  }
+}
+
+fn forecast_from_database(conn: &Connection, horizon: i64) -> Result<Vec<ForecastPoint>> {
   let today = Utc::now().date_naive();
+  let base = base_currency(conn)?;
+  let mut accounts = load_accounts(conn)?;
+  let mut items = load_scheduled_items(conn, today)?;
+
+  // Restate every balance and scheduled amount in the base currency up front so
+  // the simulation — and the summed ForecastPoint — is currency-consistent.
+  for account in accounts.values_mut() {
+    account.balance = convert_to_base(conn, account.balance, &account.currency, &base)?;
+  }
+  for item in items.iter_mut() {
+    let currency = accounts
+      .get(&item.account_id)
+      .map(|account| account.currency.clone())
+      .unwrap_or_else(|| base.clone());
+    item.amount = convert_to_base(conn, item.amount, &currency, &base)?;
+  }
+
+  let mut points = Vec::with_capacity(horizon.max(0) as usize);
+  for offset in 0..horizon {
+    let date = today + Duration::days(offset);
+
+    // (a) Fire every scheduled item due today, then advance it so recurring
+    // items can fire again later within the horizon.
+    for item in items.iter_mut() {
+      if item.next_date == date {
+        apply_scheduled_item(&mut accounts, item);
+        item.next_date = advance_date(item.next_date, &item.frequency);
+      }
+    }
+
+    // (b) Accrue one day of compounding growth on interest-bearing accounts.
+    for account in accounts.values_mut() {
+      if account.growth_rate_apr != 0.0 {
+        let daily_factor = (1.0 + account.growth_rate_apr).powf(1.0 / 365.0) - 1.0;
+        account.balance += account.balance * daily_factor;
+      }
+    }
+
+    let liquid_total: f64 = accounts
+      .values()
+      .filter(|account| account.is_liquid)
+      .map(|account| account.balance)
+      .sum();
+
+    points.push(ForecastPoint {
+      date: date.to_string(),
+      balance: (liquid_total * 100.0).round() / 100.0,
+    });
+  }
+
+  Ok(points)
+}
+
+#[tauri::command]
+fn forecast_30_days(db: State<'_, Mutex<Connection>>) -> Result<Vec<ForecastPoint>, String> {
+  let conn = db.lock().map_err(|err| err.to_string())?;
+  forecast_from_database(&conn, FORECAST_HORIZON_DAYS).map_err(|err| err.to_string())
+}
+
+/// Load the month-to-date spend already booked against each category from the
+/// `transactions` table, counting negative amounts as outflows and restating
+/// each in the `base` currency via the owning account's native currency.
+fn month_to_date_spend(
+  conn: &Connection,
+  month_prefix: &str,
+  base: &str,
+) -> Result<HashMap<String, f64>> {
+  let mut statement = conn
+    .prepare(
+      "SELECT t.category, -t.amount, a.currency FROM transactions t \
+       JOIN accounts a ON a.id = t.account_id \
+       WHERE t.amount < 0 AND t.category IS NOT NULL AND substr(t.date, 1, 7) = ?1",
+    )
+    .context("failed preparing transaction spend query")?;
+
+  let rows = statement
+    .query_map(params![month_prefix], |row| {
+      Ok((
+        row.get::<_, String>(0)?,
+        row.get::<_, f64>(1)?,
+        row.get::<_, String>(2)?,
+      ))
+    })
+    .context("failed reading recent transactions")?
+    .collect::<std::result::Result<Vec<_>, _>>()
+    .context("failed collecting transaction spend")?;
+
+  let mut spend: HashMap<String, f64> = HashMap::new();
+  for (category, amount, currency) in rows {
+    let converted = convert_to_base(conn, amount, &currency, base)?;
+    *spend.entry(category).or_insert(0.0) += converted;
+  }
+  Ok(spend)
+}
+
+fn load_budgets(conn: &Connection) -> Result<Vec<(String, f64)>> {
+  let mut statement = conn
+    .prepare("SELECT category, monthly_limit FROM budgets")
+    .context("failed preparing budget query")?;
+
+  let rows = statement
+    .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?)))
+    .context("failed reading budgets")?;
+
+  rows
+    .collect::<std::result::Result<Vec<_>, _>>()
+    .context("failed collecting budgets")
+}
+
+/// Project each budget category's cumulative spend across the horizon, resetting
+/// at calendar-month boundaries, and flag categories that cross `grace` of their
+/// limit along with the date they are projected to exceed it outright.
+fn budget_outlook_from_database(
+  conn: &Connection,
+  horizon: i64,
+  grace: f64,
+) -> Result<Vec<BudgetStatus>> {
+  let budgets = load_budgets(conn)?;
+  if budgets.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let today = Utc::now().date_naive();
+  let base = base_currency(conn)?;
+  let accounts = load_accounts(conn)?;
+  let limits: HashMap<String, f64> = budgets.iter().cloned().collect();
+  let mut items = load_scheduled_items(conn, today)?;
+
+  // Restate each scheduled amount in the base currency via its account's native
+  // currency, mirroring the forecast engine, so spend is comparable to limits.
+  for item in items.iter_mut() {
+    let currency = accounts
+      .get(&item.account_id)
+      .map(|account| account.currency.clone())
+      .unwrap_or_else(|| base.clone());
+    item.amount = convert_to_base(conn, item.amount, &currency, &base)?;
+  }
+
+  // Seed the starting month with spend that has already been recorded.
+  let mut spend = month_to_date_spend(conn, &today.format("%Y-%m").to_string(), &base)?;
+  spend.retain(|category, _| limits.contains_key(category));
 
-  (0..30)
-    .map(|offset| {
-      let date = today + Duration::days(offset);
-      let daily_change = 16.25 * offset as f64;
+  let mut peak: HashMap<String, f64> = HashMap::new();
+  let mut overage: HashMap<String, NaiveDate> = HashMap::new();
+  let mut current_month = (today.year(), today.month());
 
-      ForecastPoint {
-        date: date.to_string(),
-        balance: ((start_balance - daily_change) * 100.0).round() / 100.0,
+  for offset in 0..horizon {
+    let date = today + Duration::days(offset);
+    if (date.year(), date.month()) != current_month {
+      current_month = (date.year(), date.month());
+      spend.clear();
+    }
+
+    for item in items.iter_mut() {
+      if item.next_date != date {
+        continue;
+      }
+      if item.kind == "expense" {
+        if let Some(category) = &item.category {
+          if limits.contains_key(category) {
+            *spend.entry(category.clone()).or_insert(0.0) += item.amount;
+          }
+        }
+      }
+      item.next_date = advance_date(item.next_date, &item.frequency);
+    }
+
+    for (category, limit) in &budgets {
+      let current = spend.get(category).copied().unwrap_or(0.0);
+      let entry = peak.entry(category.clone()).or_insert(0.0);
+      if current > *entry {
+        *entry = current;
+      }
+      if current > *limit {
+        overage.entry(category.clone()).or_insert(date);
+      }
+    }
+  }
+
+  Ok(budgets
+    .into_iter()
+    .map(|(category, monthly_limit)| {
+      let projected_spend = peak.get(&category).copied().unwrap_or(0.0);
+      BudgetStatus {
+        at_risk: projected_spend >= grace * monthly_limit,
+        projected_overage_date: overage.get(&category).map(|date| date.to_string()),
+        category,
+        monthly_limit,
+        projected_spend: (projected_spend * 100.0).round() / 100.0,
       }
     })
-    .collect()
+    .collect())
+}
+
+#[tauri::command]
+fn budget_outlook(db: State<'_, Mutex<Connection>>) -> Result<Vec<BudgetStatus>, String> {
+  let conn = db.lock().map_err(|err| err.to_string())?;
+  budget_outlook_from_database(&conn, FORECAST_HORIZON_DAYS, BUDGET_GRACE_FRACTION)
+    .map_err(|err| err.to_string())
+}
+
+fn accounts_view(conn: &Connection) -> Result<AccountsView> {
+  let base = base_currency(conn)?;
+
+  let mut statement = conn
+    .prepare("SELECT id, name, balance, currency FROM accounts ORDER BY id")
+    .context("failed preparing account listing query")?;
+
+  let rows = statement
+    .query_map([], |row| {
+      Ok((
+        row.get::<_, i64>(0)?,
+        row.get::<_, String>(1)?,
+        row.get::<_, f64>(2)?,
+        row.get::<_, String>(3)?,
+      ))
+    })
+    .context("failed reading accounts")?;
+
+  let mut accounts = Vec::new();
+  for row in rows {
+    let (id, name, native_balance, currency) = row.context("failed decoding account")?;
+    let base_balance = convert_to_base(conn, native_balance, &currency, &base)?;
+    accounts.push(AccountBalance {
+      id,
+      name,
+      currency,
+      native_balance,
+      base_balance,
+    });
+  }
+
+  let total_liquid_base = liquid_starting_balance(conn)?;
+
+  Ok(AccountsView {
+    base_currency: base,
+    total_liquid_base,
+    accounts,
+  })
 }
 
-fn forecast_from_database(db_path: &Path) -> Result<Vec<ForecastPoint>> {
-  let start_balance = liquid_starting_balance(db_path)?;
-  Ok(build_forecast_points(start_balance))
+#[tauri::command]
+fn list_accounts(db: State<'_, Mutex<Connection>>) -> Result<AccountsView, String> {
+  let conn = db.lock().map_err(|err| err.to_string())?;
+  accounts_view(&conn).map_err(|err| err.to_string())
+}
+
+/// Persist a fresh batch of exchange rates, each stamped with today's date so
+/// `convert_to_base` always picks up the latest.
+fn store_fx_rates(conn: &Connection, rates: &[FxRate]) -> Result<()> {
+  let as_of = Utc::now().date_naive().to_string();
+  for rate in rates {
+    conn
+      .execute(
+        "INSERT INTO fx_rates (from_currency, to_currency, rate, as_of) VALUES (?1, ?2, ?3, ?4)",
+        params![rate.from_currency, rate.to_currency, rate.rate, as_of],
+      )
+      .context("failed storing exchange rate")?;
+  }
+  Ok(())
 }
 
 #[tauri::command]
-fn forecast_30_days(app: AppHandle) -> Result<Vec<ForecastPoint>, String> {
-  let db_path = database_path(&app).map_err(|err| err.to_string())?;
-  bootstrap_database_at(&db_path).map_err(|err| err.to_string())?;
-  forecast_from_database(&db_path).map_err(|err| err.to_string())
+fn refresh_fx_rates(
+  db: State<'_, Mutex<Connection>>,
+  rates: Vec<FxRate>,
+) -> Result<(), String> {
+  let conn = db.lock().map_err(|err| err.to_string())?;
+  store_fx_rates(&conn, &rates).map_err(|err| err.to_string())
+}
+
+/// Highest migration version this build knows how to apply.
+fn latest_schema_version() -> i64 {
+  MIGRATIONS.iter().map(|migration| migration.version).max().unwrap_or(0)
+}
+
+/// A timestamped default backup path alongside the live database.
+fn default_backup_path(app: &AppHandle) -> Result<PathBuf> {
+  let db_path = database_path(app)?;
+  let dir = db_path
+    .parent()
+    .map(Path::to_path_buf)
+    .unwrap_or_else(|| PathBuf::from("."));
+  let timestamp = Utc::now().format("%Y%m%d%H%M%S");
+  Ok(dir.join(format!("aurum-backup-{timestamp}.sqlite3")))
+}
+
+/// Reject a restore source whose schema is newer than this build understands.
+/// Older sources are allowed — they are migrated forward after the restore.
+fn validate_restore_source(src_path: &Path) -> Result<i64> {
+  let conn = Connection::open(src_path)
+    .with_context(|| format!("failed opening restore source at {}", src_path.display()))?;
+
+  let version: i64 = conn
+    .query_row("PRAGMA user_version", [], |row| row.get(0))
+    .context("failed reading restore source schema version")?;
+
+  let latest = latest_schema_version();
+  if version > latest {
+    return Err(anyhow!(
+      "backup schema version {version} is newer than supported {latest}"
+    ));
+  }
+
+  Ok(version)
+}
+
+#[tauri::command]
+fn backup_database(
+  app: AppHandle,
+  db: State<'_, Mutex<Connection>>,
+  dest_path: Option<String>,
+) -> Result<String, String> {
+  let dest = match dest_path {
+    Some(path) => PathBuf::from(path),
+    None => default_backup_path(&app).map_err(|err| err.to_string())?,
+  };
+
+  let conn = db.lock().map_err(|err| err.to_string())?;
+  conn
+    .backup(DatabaseName::Main, &dest, None)
+    .map_err(|err| err.to_string())?;
+
+  Ok(dest.display().to_string())
+}
+
+#[tauri::command]
+fn restore_database(
+  db: State<'_, Mutex<Connection>>,
+  src_path: String,
+) -> Result<String, String> {
+  let src = PathBuf::from(&src_path);
+  validate_restore_source(&src).map_err(|err| err.to_string())?;
+
+  let mut conn = db.lock().map_err(|err| err.to_string())?;
+  conn
+    .restore(
+      DatabaseName::Main,
+      &src,
+      None::<fn(rusqlite::backup::Progress)>,
+    )
+    .map_err(|err| err.to_string())?;
+
+  // A restored older snapshot may predate current migrations; bring it forward.
+  run_migrations(&mut conn).map_err(|err| err.to_string())?;
+
+  Ok(src.display().to_string())
 }
 
 pub fn run() {
@@ -143,9 +793,18 @@ pub fn run() {
     .setup(|app| {
       let db_path = database_path(app.handle())?;
       bootstrap_database_at(&db_path)?;
+      let conn = open_connection(&db_path)?;
+      app.manage(Mutex::new(conn));
       Ok(())
     })
-    .invoke_handler(tauri::generate_handler![forecast_30_days])
+    .invoke_handler(tauri::generate_handler![
+      forecast_30_days,
+      budget_outlook,
+      list_accounts,
+      refresh_fx_rates,
+      backup_database,
+      restore_database
+    ])
     .run(tauri::generate_context!())
     .expect("error while running tauri app");
 }
@@ -195,15 +854,215 @@ mod tests {
   }
 
   #[test]
-  fn forecast_returns_30_points_from_seeded_balance() {
+  fn migrations_stamp_latest_user_version() {
+    let dir = tempdir().expect("temporary directory should be created");
+    let db_path = dir.path().join("test.sqlite3");
+
+    bootstrap_database_at(&db_path).expect("database bootstrap should succeed");
+
+    let conn = Connection::open(&db_path).expect("database should open");
+    let version: i64 = conn
+      .query_row("PRAGMA user_version", [], |row| row.get(0))
+      .expect("user_version should be queryable");
+    let latest = MIGRATIONS.iter().map(|m| m.version).max().unwrap_or(0);
+    assert_eq!(version, latest);
+  }
+
+  #[test]
+  fn forecast_returns_horizon_points_from_seeded_balance() {
     let dir = tempdir().expect("temporary directory should be created");
     let db_path = dir.path().join("test.sqlite3");
 
     bootstrap_database_at(&db_path).expect("database bootstrap should succeed");
-    let forecast = forecast_from_database(&db_path).expect("forecast should be generated");
+    let conn = open_connection(&db_path).expect("connection should open");
+    let forecast = forecast_from_database(&conn, 30).expect("forecast should be generated");
 
+    // With no scheduled items and no growth the balance stays flat at the seed.
     assert_eq!(forecast.len(), 30);
     assert_eq!(forecast[0].balance, DEFAULT_ACCOUNT_BALANCE);
+    assert_eq!(forecast[29].balance, DEFAULT_ACCOUNT_BALANCE);
+  }
+
+  #[test]
+  fn liquid_balance_converts_foreign_accounts_to_base() {
+    let dir = tempdir().expect("temporary directory should be created");
+    let db_path = dir.path().join("test.sqlite3");
+
+    bootstrap_database_at(&db_path).expect("database bootstrap should succeed");
+    let conn = open_connection(&db_path).expect("connection should open");
+
+    // Seed account is 2500 USD; add a 100 EUR account and a EUR->USD rate.
+    conn
+      .execute(
+        "INSERT INTO accounts (name, type, balance, currency, is_liquid, growth_rate_apr) \
+         VALUES ('Euro Savings', 'savings', 100.0, 'EUR', 1, 0.0)",
+        [],
+      )
+      .expect("eur account should insert");
+    store_fx_rates(
+      &conn,
+      &[FxRate {
+        from_currency: "EUR".to_string(),
+        to_currency: "USD".to_string(),
+        rate: 1.1,
+      }],
+    )
+    .expect("rate should store");
+
+    let total = liquid_starting_balance(&conn).expect("balance should compute");
+    assert!((total - (DEFAULT_ACCOUNT_BALANCE + 110.0)).abs() < 1e-6);
+  }
+
+  #[test]
+  fn backup_produces_a_readable_copy() {
+    let dir = tempdir().expect("temporary directory should be created");
+    let db_path = dir.path().join("test.sqlite3");
+
+    bootstrap_database_at(&db_path).expect("database bootstrap should succeed");
+    let conn = open_connection(&db_path).expect("connection should open");
+
+    let dest = dir.path().join("backup.sqlite3");
+    conn
+      .backup(DatabaseName::Main, &dest, None)
+      .expect("online backup should succeed");
+
+    // The copy validates against the known migration range and carries the seed.
+    let version = validate_restore_source(&dest).expect("backup should validate");
+    assert_eq!(version, latest_schema_version());
+
+    let copy = Connection::open(&dest).expect("backup should open");
+    let account_count: i64 = copy
+      .query_row("SELECT COUNT(1) FROM accounts", [], |row| row.get(0))
+      .expect("account count should be queryable");
+    assert_eq!(account_count, 1);
+  }
+
+  #[test]
+  fn budget_outlook_flags_category_that_exceeds_limit() {
+    let dir = tempdir().expect("temporary directory should be created");
+    let db_path = dir.path().join("test.sqlite3");
+
+    bootstrap_database_at(&db_path).expect("database bootstrap should succeed");
+    let conn = open_connection(&db_path).expect("connection should open");
+
+    let account_id: i64 = conn
+      .query_row("SELECT id FROM accounts LIMIT 1", [], |row| row.get(0))
+      .expect("seed account id should be queryable");
+    conn
+      .execute(
+        "INSERT INTO budgets (category, monthly_limit) VALUES ('Groceries', 100.0)",
+        [],
+      )
+      .expect("budget should insert");
+    let today = Utc::now().date_naive().to_string();
+    conn
+      .execute(
+        "INSERT INTO scheduled_items (account_id, amount, frequency, next_date, type, target_account_id, category) \
+         VALUES (?1, 20.0, 'daily', ?2, 'expense', NULL, 'Groceries')",
+        params![account_id, today],
+      )
+      .expect("scheduled expense should insert");
+
+    let outlook = budget_outlook_from_database(&conn, 30, BUDGET_GRACE_FRACTION)
+      .expect("budget outlook should compute");
+
+    let groceries = outlook
+      .iter()
+      .find(|status| status.category == "Groceries")
+      .expect("groceries budget should be present");
+    assert!(groceries.at_risk);
+    assert!(groceries.projected_spend > 100.0);
+    assert!(groceries.projected_overage_date.is_some());
+  }
+
+  #[test]
+  fn budget_outlook_converts_foreign_spend_to_base() {
+    let dir = tempdir().expect("temporary directory should be created");
+    let db_path = dir.path().join("test.sqlite3");
+
+    bootstrap_database_at(&db_path).expect("database bootstrap should succeed");
+    let conn = open_connection(&db_path).expect("connection should open");
+
+    // A EUR account whose grocery expense must be converted into the USD budget.
+    conn
+      .execute(
+        "INSERT INTO accounts (name, type, balance, currency, is_liquid, growth_rate_apr) \
+         VALUES ('Euro Checking', 'checking', 1000.0, 'EUR', 1, 0.0)",
+        [],
+      )
+      .expect("eur account should insert");
+    let account_id: i64 = conn
+      .query_row(
+        "SELECT id FROM accounts WHERE currency = 'EUR' LIMIT 1",
+        [],
+        |row| row.get(0),
+      )
+      .expect("eur account id should be queryable");
+    store_fx_rates(
+      &conn,
+      &[FxRate {
+        from_currency: "EUR".to_string(),
+        to_currency: "USD".to_string(),
+        rate: 1.1,
+      }],
+    )
+    .expect("rate should store");
+    conn
+      .execute(
+        "INSERT INTO budgets (category, monthly_limit) VALUES ('Groceries', 100.0)",
+        [],
+      )
+      .expect("budget should insert");
+    let today = Utc::now().date_naive().to_string();
+    // Monthly frequency fires exactly once per calendar month, so the per-month
+    // peak is a single 150 EUR expense regardless of the run date. This pins the
+    // monthly-reset contract: projected_spend is one month's spend, not the sum
+    // of every fire across the horizon.
+    conn
+      .execute(
+        "INSERT INTO scheduled_items (account_id, amount, frequency, next_date, type, target_account_id, category) \
+         VALUES (?1, 150.0, 'monthly', ?2, 'expense', NULL, 'Groceries')",
+        params![account_id, today],
+      )
+      .expect("scheduled expense should insert");
+
+    let outlook = budget_outlook_from_database(&conn, 30, BUDGET_GRACE_FRACTION)
+      .expect("budget outlook should compute");
+
+    let groceries = outlook
+      .iter()
+      .find(|status| status.category == "Groceries")
+      .expect("groceries budget should be present");
+    // Peak month = 1 fire × 150 EUR × 1.1 = 165 USD (converted, not 150).
+    assert!((groceries.projected_spend - 165.0).abs() < 1e-6);
+    assert!(groceries.at_risk);
+    assert!(groceries.projected_overage_date.is_some());
+  }
+
+  #[test]
+  fn forecast_draws_down_on_recurring_expense() {
+    let dir = tempdir().expect("temporary directory should be created");
+    let db_path = dir.path().join("test.sqlite3");
+
+    bootstrap_database_at(&db_path).expect("database bootstrap should succeed");
+
+    let conn = open_connection(&db_path).expect("connection should open");
+    let account_id: i64 = conn
+      .query_row("SELECT id FROM accounts LIMIT 1", [], |row| row.get(0))
+      .expect("seed account id should be queryable");
+    let today = Utc::now().date_naive().to_string();
+    conn
+      .execute(
+        "INSERT INTO scheduled_items (account_id, amount, frequency, next_date, type, target_account_id) \
+         VALUES (?1, ?2, 'daily', ?3, 'expense', NULL)",
+        params![account_id, 10.0, today],
+      )
+      .expect("scheduled expense should insert");
+
+    let forecast = forecast_from_database(&conn, 30).expect("forecast should be generated");
+
+    assert_eq!(forecast.len(), 30);
+    assert_eq!(forecast[0].balance, DEFAULT_ACCOUNT_BALANCE - 10.0);
     assert!(forecast[29].balance < forecast[0].balance);
   }
 }